@@ -3,55 +3,66 @@
 //! A terminal UI is at its root a lot of strings. In order to make it accessible and stylish,
 //! those strings may be associated to a set of styles. `tui` has three ways to represent them:
 //! - A single line string where all graphemes have the same style is represented by a [`Span`].
-//! - A single line string where each grapheme may have its own style is represented by [`Spans`].
+//! - A single line string where each grapheme may have its own style is represented by a
+//! [`Line`].
 //! - A multiple line string where each grapheme may have its own style is represented by a
 //! [`Text`].
+//! - Content that should not be displayed as-is, such as a password, is represented by a
+//! [`Masked`].
 //!
-//! These types form a hierarchy: [`Spans`] is a collection of [`Span`] and each line of [`Text`]
-//! is a [`Spans`].
+//! These types form a hierarchy: [`Line`] is a collection of [`Span`] and each line of [`Text`]
+//! is a [`Line`]. [`Spans`] is a deprecated type with the same shape, kept around for existing
+//! call sites; new code should use [`Line`]. The two are distinct types that convert into each
+//! other with `From`, so one cannot be passed where the other is expected (e.g. [`Text::lines`]
+//! still holds [`Spans`]).
 //!
 //! Keep it mind that a lot of widgets will use those types to advertise what kind of string is
 //! supported for their properties. Moreover, `tui` provides convenient `From` implementations so
 //! that you can start by using simple `String` or `&str` and then promote them to the previous
 //! primitives when you need additional styling capabilities.
 //!
-//! For example, for the [`crate::widgets::Block`] widget, all the following calls are valid to set
-//! its `title` property (which is a [`Spans`] under the hood):
+//! For example, a widget property that accepts `impl Into<Line>` can be given a plain string,
+//! a single styled [`Span`], or a `Vec<Span>`, and all of them are promoted to a [`Line`]:
 //!
 //! ```rust
-//! # use tui::widgets::Block;
-//! # use tui::text::{Span, Spans};
+//! # use tui::text::{Line, Span};
 //! # use tui::style::{Color, Style};
 //! // A simple string with no styling.
-//! // Converted to Spans(vec![
+//! // Converted to Line(vec![
 //! //   Span { content: Cow::Borrowed("My title"), style: Style { .. } }
 //! // ])
-//! let block = Block::default().title("My title");
+//! let title: Line = "My title".into();
 //!
 //! // A simple string with a unique style.
-//! // Converted to Spans(vec![
+//! // Converted to Line(vec![
 //! //   Span { content: Cow::Borrowed("My title"), style: Style { fg: Some(Color::Yellow), .. }
 //! // ])
-//! let block = Block::default().title(
-//!     Span::styled("My title", Style::default().fg(Color::Yellow))
-//! );
+//! let title: Line = Span::styled("My title", Style::default().fg(Color::Yellow)).into();
 //!
 //! // A string with multiple styles.
-//! // Converted to Spans(vec![
+//! // Converted to Line(vec![
 //! //   Span { content: Cow::Borrowed("My"), style: Style { fg: Some(Color::Yellow), .. } },
 //! //   Span { content: Cow::Borrowed(" title"), .. }
 //! // ])
-//! let block = Block::default().title(vec![
+//! let title = Line::from(vec![
 //!     Span::styled("My", Style::default().fg(Color::Yellow)),
 //!     Span::raw(" title"),
 //! ]);
 //! ```
+// `Spans` is kept around as a deprecated counterpart of `Line` (see below); allow its use within this
+// module so the deprecation only surfaces to downstream callers.
+#![allow(deprecated)]
+
 use crate::style::Style;
 use std::borrow::Cow;
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
-const NBSP: &str = "\u{00a0}";
+mod ansi;
+mod masked;
+pub mod reflow;
+pub use masked::Masked;
+use reflow::LineComposer;
 
 /// A grapheme associated to a style.
 #[derive(Debug, Clone, PartialEq)]
@@ -108,6 +119,19 @@ impl<'a> Span<'a> {
         }
     }
 
+    /// Parses a single line of `ESC[ ... m` SGR escape sequences into a styled [`Span`],
+    /// concatenating the text of every run and collapsing all styling into that of the first
+    /// styled run.
+    ///
+    /// This is a convenience for callers that know `content` carries at most one style; use
+    /// [`Spans::from_ansi`] or [`Text::from_ansi`] to preserve per-run styling.
+    pub fn from_ansi(content: &str) -> Span<'static> {
+        let spans = ansi::parse_line(content, Style::default()).0 .0;
+        let style = spans.first().map(|span| span.style).unwrap_or_default();
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        Span::styled(text, style)
+    }
+
     /// Returns the width of the content held by this span.
     pub fn width(&self) -> usize {
         self.content.width()
@@ -181,30 +205,6 @@ impl<'a> Span<'a> {
             })
             .filter(|s| s.symbol != "\n")
     }
-
-    fn split_at_in_place(&mut self, mid: usize) -> Span<'a> {
-        let content = match self.content {
-            Cow::Owned(ref mut s) => {
-                let start = s.char_indices().map(|(i, _)| i).nth(mid).unwrap();
-                let s2 = s[start..].to_string();
-                s.truncate(start);
-                Cow::Owned(s2)
-            }
-            Cow::Borrowed(s) => {
-                let (s1, s2) = s.split_at(mid);
-                self.content = Cow::Borrowed(s1);
-                Cow::Borrowed(s2)
-            }
-        };
-        Span {
-            content,
-            style: self.style,
-        }
-    }
-
-    fn trim_start(&mut self) {
-        self.content = Cow::Owned(String::from(self.content.trim_start()));
-    }
 }
 
 impl<'a> From<String> for Span<'a> {
@@ -220,6 +220,104 @@ impl<'a> From<&'a str> for Span<'a> {
 }
 
 /// A string composed of clusters of graphemes, each with their own style.
+///
+/// This is the canonical type for a single line of styled text; [`Spans`] is a separate,
+/// deprecated type of the same shape kept for existing call sites, convertible to and from
+/// [`Line`] with `From`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line<'a>(pub Vec<Span<'a>>);
+
+impl<'a> Default for Line<'a> {
+    fn default() -> Line<'a> {
+        Line(Vec::new())
+    }
+}
+
+impl<'a> Line<'a> {
+    /// Returns the width of the underlying string.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use tui::text::{Span, Line};
+    /// # use tui::style::{Color, Style};
+    /// let line = Line::from(vec![
+    ///     Span::styled("My", Style::default().fg(Color::Yellow)),
+    ///     Span::raw(" text"),
+    /// ]);
+    /// assert_eq!(7, line.width());
+    /// ```
+    pub fn width(&self) -> usize {
+        self.0.iter().map(Span::width).sum()
+    }
+
+    /// Parses a single line of ANSI/SGR escape sequences (e.g. compiler diagnostics or the
+    /// output of `ls --color`) into a styled [`Line`].
+    ///
+    /// See [`Text::from_ansi`] for details on the supported escape sequences. Any `\n` found in
+    /// `input` is treated as plain text rather than a line break; use [`Text::from_ansi`] for
+    /// multi-line input.
+    pub fn from_ansi(input: &str) -> Line<'static> {
+        ansi::parse_line(input, Style::default()).0.into()
+    }
+}
+
+impl<'a> From<String> for Line<'a> {
+    fn from(s: String) -> Line<'a> {
+        Line(vec![Span::from(s)])
+    }
+}
+
+impl<'a> From<&'a str> for Line<'a> {
+    fn from(s: &'a str) -> Line<'a> {
+        Line(vec![Span::from(s)])
+    }
+}
+
+impl<'a> From<Vec<Span<'a>>> for Line<'a> {
+    fn from(spans: Vec<Span<'a>>) -> Line<'a> {
+        Line(spans)
+    }
+}
+
+impl<'a> From<Span<'a>> for Line<'a> {
+    fn from(span: Span<'a>) -> Line<'a> {
+        Line(vec![span])
+    }
+}
+
+impl<'a> From<Line<'a>> for String {
+    fn from(line: Line<'a>) -> String {
+        line.0.iter().fold(String::new(), |mut acc, s| {
+            acc.push_str(s.content.as_ref());
+            acc
+        })
+    }
+}
+
+impl<'a> IntoIterator for Line<'a> {
+    type Item = Span<'a>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> From<Spans<'a>> for Line<'a> {
+    fn from(spans: Spans<'a>) -> Line<'a> {
+        Line(spans.0)
+    }
+}
+
+impl<'a> From<Line<'a>> for Spans<'a> {
+    fn from(line: Line<'a>) -> Spans<'a> {
+        Spans(line.0)
+    }
+}
+
+/// A string composed of clusters of graphemes, each with their own style.
+#[deprecated(since = "0.20.0", note = "`Spans` has been renamed to `Line`; use `Line` instead")]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Spans<'a>(pub Vec<Span<'a>>);
 
@@ -246,6 +344,31 @@ impl<'a> Spans<'a> {
     pub fn width(&self) -> usize {
         self.0.iter().map(Span::width).sum()
     }
+
+    /// Parses a single line of ANSI/SGR escape sequences (e.g. compiler diagnostics or the
+    /// output of `ls --color`) into styled [`Spans`].
+    ///
+    /// See [`Text::from_ansi`] for details on the supported escape sequences. Any `\n` found in
+    /// `input` is treated as plain text rather than a line break; use [`Text::from_ansi`] for
+    /// multi-line input.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use tui::text::Spans;
+    /// # use tui::style::{Color, Style};
+    /// let spans = Spans::from_ansi("\u{1b}[31mHello\u{1b}[0m");
+    /// assert_eq!(
+    ///     spans,
+    ///     Spans::from(vec![tui::text::Span::styled(
+    ///         "Hello",
+    ///         Style::default().fg(Color::Red)
+    ///     )])
+    /// );
+    /// ```
+    pub fn from_ansi(input: &str) -> Spans<'static> {
+        ansi::parse_line(input, Style::default()).0
+    }
 }
 
 impl<'a> From<String> for Spans<'a> {
@@ -397,6 +520,25 @@ impl<'a> Text<'a> {
         self.lines.len()
     }
 
+    /// Parses `content` for `ESC[ ... m` SGR escape sequences (as produced by compiler
+    /// diagnostics, `ls --color`, `git`, etc.) into styled [`Text`].
+    ///
+    /// A new [`Span`] is started every time the active style changes, and the input is split
+    /// into separate [`Spans`] on `\n`. Unknown or malformed escape sequences are skipped
+    /// silently so arbitrary program output never breaks rendering.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use tui::text::{Span, Spans, Text};
+    /// # use tui::style::{Color, Style};
+    /// let text = Text::from_ansi("\u{1b}[1;31merror\u{1b}[0m: oops\n\u{1b}[32mok\u{1b}[0m");
+    /// assert_eq!(2, text.height());
+    /// ```
+    pub fn from_ansi(content: &str) -> Text<'static> {
+        ansi::parse_text(content)
+    }
+
     /// Apply a new style to existing text.
     ///
     /// # Examples
@@ -474,15 +616,18 @@ impl<'a> Extend<Spans<'a>> for Text<'a> {
     }
 }
 
+/// Greedily word-wraps [`Spans`] pushed into it to a fixed `width`, one paragraph (one
+/// [`Extend`]ed [`Spans`]) at a time.
+///
+/// Internally this drives a [`reflow::WordWrapper`] over the [`StyledGrapheme`]s of each
+/// paragraph, so the wrapping rules (trimming, NBSP joining, force-breaking overly wide
+/// graphemes) live in [`reflow`] and are shared with [`reflow::LineTruncator`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct WrappedText<'a> {
     text: Text<'a>,
     trim: bool,
     width: u16,
-    column: u16,
-    last_word_end: u16,
-    was_whitespace: bool,
-    was_linebreak:bool,
+    optimal: bool,
 }
 
 impl<'a> WrappedText<'a> {
@@ -491,10 +636,7 @@ impl<'a> WrappedText<'a> {
             text: Text::default(),
             width,
             trim: true,
-            column: 0,
-            last_word_end: 0,
-            was_whitespace: false,
-            was_linebreak:false,
+            optimal: false,
         }
     }
 
@@ -503,80 +645,261 @@ impl<'a> WrappedText<'a> {
         self
     }
 
-    fn push_span(&mut self, span: Span<'a>) {
-        if self.text.lines.is_empty() {
+    /// Enables minimum-raggedness (Knuth-Plass-style) line breaking instead of the default
+    /// greedy word wrap.
+    ///
+    /// Once all the spans of a paragraph have been pushed, breaks are chosen to minimize the
+    /// total unevenness of the resulting lines rather than simply filling each line as full as
+    /// possible. See [`wrap_paragraph_optimal`] for the cost model; this is O(n²) in the number
+    /// of words, so it is best suited to paragraphs of bounded size.
+    pub fn optimal(mut self, optimal: bool) -> Self {
+        self.optimal = optimal;
+        self
+    }
+
+    fn push_spans(&mut self, spans: Spans<'a>) {
+        let symbols = spans
+            .0
+            .iter()
+            .flat_map(|span| {
+                UnicodeSegmentation::graphemes(span.content.as_ref(), true)
+                    .map(move |g| StyledGrapheme {
+                        symbol: g,
+                        style: span.style,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        if self.optimal {
+            for segment in symbols.split(|g| g.symbol == "\n") {
+                self.text
+                    .lines
+                    .extend(wrap_paragraph_optimal(segment, self.width, self.trim));
+            }
+            return;
+        }
+
+        let mut symbols = symbols.into_iter();
+        let mut wrapper = reflow::WordWrapper::new(&mut symbols, self.width, self.trim);
+        let mut produced_line = false;
+        while let Some((line, _width)) = wrapper.next_line() {
+            produced_line = true;
+            self.text.lines.push(Spans(styled_graphemes_to_spans(line)));
+        }
+        if !produced_line {
             self.text.lines.push(Spans::default());
         }
-        let last_line = self.text.lines.len() - 1;
-        self.text.lines[last_line].0.push(span);
     }
+}
 
-    fn push_spans<T>(&mut self, spans: T)
-    where
-        T: IntoIterator<Item = Span<'a>>,
-    {
-        let mut iter = spans.into_iter();
-        let mut pending_span = iter.next();
-        while let Some(mut span) = pending_span.take() {
-            let span_position = self.column;
-            let mut breakpoint = None;
-            // Skip leading whitespaces when trim is enabled
-            if self.column == 0 && self.trim {
-                span.trim_start();
+/// One word of a paragraph being laid out by [`wrap_paragraph_optimal`]: the grapheme range
+/// `start..end` it spans. A word force-broken into grapheme chunks (because it was wider than
+/// the target width) becomes several adjacent [`OptimalWord`]s with no grapheme gap between
+/// them, which is how the DP tells them apart from two real, whitespace-separated words without
+/// needing its own flag: the gap is measured from the segment, not assumed.
+struct OptimalWord {
+    start: usize,
+    end: usize,
+}
+
+/// Pushes `segment[start..end]` onto `words` as one [`OptimalWord`], or as several if it is wider
+/// than `width` and must be force-broken into grapheme chunks first, so that no single word (be
+/// it an ordinary word or the leading whitespace run kept when `trim` is `false`) can ever make a
+/// line infeasible on its own.
+fn push_optimal_word(
+    words: &mut Vec<OptimalWord>,
+    segment: &[StyledGrapheme],
+    start: usize,
+    end: usize,
+    width: u16,
+) {
+    // Widened to `u64` (matching `gap`/`prefix` below): an unbroken run of graphemes with no
+    // whitespace at all (e.g. a long URL or hash fed through as one "word") can be far wider
+    // than `u16::MAX`, and summing into a `u16` here would overflow before the width check below
+    // gets a chance to decide it needs splitting.
+    let word_width: u64 = segment[start..end].iter().map(|g| g.symbol.width() as u64).sum();
+    if word_width <= width as u64 {
+        words.push(OptimalWord { start, end });
+        return;
+    }
+    let mut chunk_start = start;
+    let mut chunk_width = 0u16;
+    for (idx, grapheme) in segment.iter().enumerate().take(end).skip(start) {
+        let grapheme_width = grapheme.symbol.width() as u16;
+        if chunk_width > 0 && chunk_width + grapheme_width > width {
+            words.push(OptimalWord {
+                start: chunk_start,
+                end: idx,
+            });
+            chunk_start = idx;
+            chunk_width = 0;
+        }
+        chunk_width += grapheme_width;
+    }
+    words.push(OptimalWord {
+        start: chunk_start,
+        end,
+    });
+}
+
+/// Chooses line breaks for one hard-broken segment of a paragraph (i.e. with no embedded `\n`)
+/// that minimize the total squared "raggedness" of the produced lines, using a Knuth-Plass-style
+/// dynamic program over whole words.
+///
+/// The paragraph is modeled as a sequence of words with display widths `w[0..n]`, each followed
+/// by the real display width of whatever whitespace separated it from the next word (`0` between
+/// force-split chunks of the same overlong word, since they're adjacent by construction).
+/// `cost[i]`, the minimum total badness to lay out the first `i` words, is `cost[0] = 0` and
+/// `cost[i] = min` over feasible `j` of `cost[j] + badness(j, i)`, where a line spanning words
+/// `j..i` has used width `L` equal to the sum of those words' widths and the real gaps between
+/// them (excluding the gap after the last word, which becomes the line break and is dropped, and,
+/// when `trim` is `false`, including the gap before the first word, which the line keeps);
+/// lines with `L > width` are infeasible unless they hold a single word, and badness is
+/// `(width - L)^2` for every line but the last (which reaches the end of the paragraph and so
+/// costs nothing). Words wider than `width` are pre-split into grapheme chunks of at most `width`
+/// (a lone grapheme wider than `width` becomes a chunk of its own) before the dynamic program
+/// runs, so that no line is ever infeasible by construction.
+///
+/// Whitespace separating two chosen lines is dropped when `trim` is `true`, the same as a
+/// trimmed line from [`reflow::WordWrapper`]. When `trim` is `false`, that whitespace is instead
+/// kept as the leading whitespace of the line that follows it — matching `WordWrapper`, which
+/// preserves a line's own leading whitespace for every line it produces, not only the first.
+fn wrap_paragraph_optimal<'g, 'a>(
+    segment: &[StyledGrapheme<'g>],
+    width: u16,
+    trim: bool,
+) -> Vec<Spans<'a>> {
+    if width == 0 {
+        return vec![Spans::default()];
+    }
+
+    let mut words = vec![];
+    let mut i = 0;
+    if !trim {
+        while i < segment.len() && reflow::is_whitespace(segment[i].symbol) {
+            i += 1;
+        }
+        if i > 0 {
+            push_optimal_word(&mut words, segment, 0, i, width);
+        }
+    }
+    while i < segment.len() {
+        if reflow::is_whitespace(segment[i].symbol) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < segment.len() && !reflow::is_whitespace(segment[i].symbol) {
+            i += 1;
+        }
+        push_optimal_word(&mut words, segment, start, i, width);
+    }
+
+    if words.is_empty() {
+        return vec![Spans::default()];
+    }
+
+    // Prefix sums let the width of any candidate line `words[j..i]` be computed in O(1), which
+    // is what keeps the DP below at O(n²) rather than O(n³). `gap[k]` is the real display width
+    // of whitespace between `words[k]` and `words[k + 1]` (measured from the original segment,
+    // not assumed); `prefix[i]` accumulates each word's own width plus the gap after it, so a
+    // line spanning `words[j..i]` costs `prefix[i] - prefix[j] - gap[i - 1]`, dropping the
+    // trailing gap that becomes the line break instead of being part of the line.
+    let n = words.len();
+    let mut gap = vec![0u64; n];
+    for k in 0..n.saturating_sub(1) {
+        gap[k] = segment[words[k].end..words[k + 1].start]
+            .iter()
+            .map(|g| g.symbol.width() as u64)
+            .sum();
+    }
+    let mut prefix = vec![0u64; n + 1];
+    for k in 0..n {
+        let word_width: u64 = segment[words[k].start..words[k].end]
+            .iter()
+            .map(|g| g.symbol.width() as u64)
+            .sum();
+        prefix[k + 1] = prefix[k] + word_width + gap[k];
+    }
+
+    let mut cost = vec![u64::MAX; n + 1];
+    let mut break_at = vec![0; n + 1];
+    cost[0] = 0;
+    for i in 1..=n {
+        for j in 0..i {
+            if cost[j] == u64::MAX {
+                continue;
+            }
+            let mut line_width = prefix[i] - prefix[j] - gap[i - 1];
+            // An untrimmed line keeps the whitespace before its first word, so count it too.
+            if !trim && j > 0 {
+                line_width += gap[j - 1];
             }
-            for grapheme in UnicodeSegmentation::graphemes(span.content.as_ref(), true) {
-                let grapheme_width = grapheme.width() as u16;
-                // Ignore grapheme that are larger than the allowed width
-                if grapheme_width > self.width {
-                    continue;
-                }
-                if !self.was_linebreak && grapheme =="\n"
-                {
-                    let width = self.last_word_end.saturating_sub(span_position) as usize;
-                    breakpoint = Some(width+1);
-                    self.was_linebreak =true;
-                    break;
-                }
-                let is_whitespace = grapheme.chars().all(&char::is_whitespace);
-                if  !self.was_whitespace && grapheme != NBSP {
-                    self.last_word_end = self.column;
-                }
-                let next_column = self.column.saturating_add(grapheme_width);
-                if next_column > self.width {
-                    let width = self.last_word_end.saturating_sub(span_position) as usize;
-                    breakpoint = Some(width);
-                    break;
-                }
-                self.column = next_column;
-                self.was_whitespace = is_whitespace;
+            if line_width > width as u64 && i - j > 1 {
+                continue;
             }
-            if let Some(b) = breakpoint {
-                pending_span = if b > 0 {
-                    let new_span = span.split_at_in_place(b);
-                    self.push_span(span);
-                    Some(new_span)
-                } else {
-                    Some(span)
-                };
-                self.start_new_line();
+            let badness = if i == n {
+                0
             } else {
-                self.push_span(span);
-                pending_span = iter.next();
+                (width as u64).saturating_sub(line_width).pow(2)
+            };
+            let total = cost[j] + badness;
+            if total < cost[i] {
+                cost[i] = total;
+                break_at[i] = j;
             }
         }
     }
 
-    fn start_new_line(&mut self) {
-        self.column = 0;
-        self.last_word_end = 0;
-        self.text.lines.push(Spans::default());
+    let mut breaks = vec![n];
+    let mut i = n;
+    while i > 0 {
+        i = break_at[i];
+        breaks.push(i);
+    }
+    breaks.reverse();
+
+    breaks
+        .windows(2)
+        .map(|pair| {
+            let (j, i) = (pair[0], pair[1]);
+            // A trimmed line drops the whitespace that separated it from the previous line, the
+            // same as a trimmed `WordWrapper` line. When `trim` is `false`, that whitespace is a
+            // line's own leading whitespace and `WordWrapper` keeps it for *every* line it
+            // produces, not just the first, so mirror that here instead of only handling the
+            // very start of the paragraph.
+            let start = if !trim && j > 0 {
+                words[j - 1].end
+            } else {
+                words[j].start
+            };
+            Spans(styled_graphemes_to_spans(&segment[start..words[i - 1].end]))
+        })
+        .collect()
+}
+
+fn styled_graphemes_to_spans<'g, 'a>(graphemes: &[StyledGrapheme<'g>]) -> Vec<Span<'a>> {
+    let mut spans = vec![];
+    let mut current_style = None;
+    let mut current = String::new();
+    for grapheme in graphemes {
+        if current_style != Some(grapheme.style) {
+            if let Some(style) = current_style.take() {
+                spans.push(Span::styled(std::mem::take(&mut current), style));
+            }
+            current_style = Some(grapheme.style);
+        }
+        current.push_str(grapheme.symbol);
     }
+    if let Some(style) = current_style {
+        spans.push(Span::styled(current, style));
+    }
+    spans
 }
 
 impl<'a> Extend<Spans<'a>> for WrappedText<'a> {
     fn extend<T: IntoIterator<Item = Spans<'a>>>(&mut self, iter: T) {
         for spans in iter {
-            self.start_new_line();
             self.push_spans(spans);
         }
     }
@@ -631,4 +954,75 @@ mod tests {
 //         ]);
 //         assert_eq!(expected, Text::from(t));
 //     }
+
+    #[test]
+    fn masked_preserves_display_width_of_wide_graphemes() {
+        let masked = Masked::new("a漢b", '*');
+        // "漢" has a display width of 2, so it must be masked by two `*`s, not one, to keep the
+        // masked form the same number of columns wide as the real content.
+        assert_eq!("****", masked.to_string());
+        assert_eq!("a漢b", masked.value());
+    }
+
+    #[test]
+    fn span_from_ansi_keeps_text_across_style_changes() {
+        let span = Span::from_ansi("\u{1b}[31mHello\u{1b}[0m World");
+        assert_eq!("Hello World", span.content);
+        assert_eq!(Style::default().fg(Color::Red), span.style);
+    }
+
+    #[test]
+    fn text_from_ansi_skips_invalid_escape() {
+        let text = Text::from_ansi("abc\u{1b}[1foo end without m");
+        assert_eq!(Text::from("abcfoo end without m"), text);
+    }
+
+    #[test]
+    fn optimal_wrap_measures_real_gaps_between_words() {
+        let mut t = WrappedText::new(5).optimal(true);
+        // Three spaces between the words, not the single space the cost model assumes; a line
+        // holding both words would really be 7 columns wide, which doesn't fit in 5.
+        t.extend(Text::from("ab   cd"));
+        let t = Text::from(t);
+        for line in &t.lines {
+            assert!(line.width() <= 5, "line {:?} is wider than 5", line);
+        }
+        assert_eq!(Text::from(vec![Spans::from("ab"), Spans::from("cd")]), t);
+    }
+
+    #[test]
+    fn optimal_wrap_force_splits_overlong_leading_whitespace() {
+        let mut t = WrappedText::new(3).trim(false).optimal(true);
+        t.extend(Text::from("          word"));
+        let t = Text::from(t);
+        for line in &t.lines {
+            assert!(line.width() <= 3, "line {:?} is wider than 3", line);
+        }
+    }
+
+    #[test]
+    fn optimal_wrap_keeps_interior_whitespace_per_line_when_untrimmed() {
+        let mut t = WrappedText::new(5).trim(false).optimal(true);
+        t.extend(Text::from("aaa  bbb  ccc"));
+        let t = Text::from(t);
+        let expected = Text::from(vec![
+            Spans::from("aaa"),
+            Spans::from("  bbb"),
+            Spans::from("  ccc"),
+        ]);
+        assert_eq!(expected, t);
+    }
+
+    #[test]
+    fn optimal_wrap_handles_unbroken_word_wider_than_u16_max() {
+        // A single run of graphemes with no whitespace at all (e.g. a long URL or hash) must not
+        // overflow the `u16` word-width accumulator while deciding whether it needs splitting.
+        let long_word = "a".repeat(u16::MAX as usize + 1);
+        let mut t = WrappedText::new(10).optimal(true);
+        t.extend(Text::from(Spans::from(long_word)));
+        let t = Text::from(t);
+        for line in &t.lines {
+            assert!(line.width() <= 10, "line {:?} is wider than 10", line);
+        }
+    }
  }
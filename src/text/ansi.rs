@@ -0,0 +1,154 @@
+//! Parsing of ANSI/SGR escape sequences into [`Text`].
+
+use crate::style::{Color, Modifier, Style};
+use crate::text::{Span, Spans, Text};
+
+/// Parses `input` for `ESC[ ... m` SGR escape sequences, turning it into a styled [`Text`].
+///
+/// A new [`Span`] is started every time the active [`Style`] changes, and the input is split
+/// into separate [`Spans`] on `\n`. Unknown or malformed escape sequences are skipped silently
+/// so that arbitrary program output never breaks rendering.
+pub fn parse_text(input: &str) -> Text<'static> {
+    let mut lines = vec![];
+    let mut style = Style::default();
+    for line in input.split('\n') {
+        let (spans, next_style) = parse_line(line, style);
+        lines.push(spans);
+        style = next_style;
+    }
+    Text::from(lines)
+}
+
+/// Parses a single line (no `\n` handling) of `ESC[ ... m` SGR escape sequences into styled
+/// [`Spans`], starting from `style`. Returns the parsed [`Spans`] along with the [`Style`] still
+/// active at the end of the line, so callers can carry styling across lines.
+pub fn parse_line(input: &str, mut style: Style) -> (Spans<'static>, Style) {
+    let mut spans = vec![];
+    let mut current = String::new();
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            let mut seq = String::new();
+            let mut terminated = false;
+            // Valid SGR parameters are digits and `;` only; anything else (or running out of
+            // input) before the closing `m` means this wasn't really an SGR escape, so stop
+            // scanning without consuming the offending character.
+            while let Some(&next) = chars.peek() {
+                if next == 'm' {
+                    chars.next();
+                    terminated = true;
+                    break;
+                }
+                if next.is_ascii_digit() || next == ';' {
+                    seq.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            // Not a valid SGR escape: the `ESC[` and whatever parameters were scanned are skipped
+            // silently. Whatever broke the scan (an unexpected character, or the end of input) is
+            // left in `chars` to be processed normally on the next iteration.
+            if terminated {
+                if !current.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut current), style));
+                }
+                style = apply_sgr(style, &seq);
+            }
+            continue;
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style));
+    }
+    (Spans::from(spans), style)
+}
+
+/// Applies the parameters of a single SGR sequence (the part between `ESC[` and `m`) to `style`,
+/// returning the resulting style. Unknown or malformed parameters are ignored.
+fn apply_sgr(mut style: Style, seq: &str) -> Style {
+    let params: Vec<&str> = if seq.is_empty() { vec!["0"] } else { seq.split(';').collect() };
+    let mut i = 0;
+    while i < params.len() {
+        let code: u16 = match params[i].parse() {
+            Ok(n) => n,
+            Err(_) => {
+                i += 1;
+                continue;
+            }
+        };
+        match code {
+            0 => style = Style::default(),
+            1 => style = style.add_modifier(Modifier::BOLD),
+            22 => style = style.remove_modifier(Modifier::BOLD),
+            3 => style = style.add_modifier(Modifier::ITALIC),
+            23 => style = style.remove_modifier(Modifier::ITALIC),
+            4 => style = style.add_modifier(Modifier::UNDERLINED),
+            24 => style = style.remove_modifier(Modifier::UNDERLINED),
+            7 => style = style.add_modifier(Modifier::REVERSED),
+            27 => style = style.remove_modifier(Modifier::REVERSED),
+            30..=37 => style = style.fg(palette_color(code - 30, false)),
+            90..=97 => style = style.fg(palette_color(code - 90, true)),
+            39 => style.fg = None,
+            40..=47 => style = style.bg(palette_color(code - 40, false)),
+            100..=107 => style = style.bg(palette_color(code - 100, true)),
+            49 => style.bg = None,
+            38 | 48 => {
+                if let Some((color, consumed)) = extended_color(&params[i + 1..]) {
+                    if code == 38 {
+                        style = style.fg(color);
+                    } else {
+                        style = style.bg(color);
+                    }
+                    i += consumed;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    style
+}
+
+/// Parses the `5;n` (indexed) or `2;r;g;b` (truecolor) parameters that follow a `38`/`48` code,
+/// returning the resulting color and the number of extra parameters consumed.
+fn extended_color(params: &[&str]) -> Option<(Color, usize)> {
+    match params.first() {
+        Some(&"5") => {
+            let n: u8 = params.get(1)?.parse().ok()?;
+            Some((Color::Indexed(n), 2))
+        }
+        Some(&"2") => {
+            let r: u8 = params.get(1)?.parse().ok()?;
+            let g: u8 = params.get(2)?.parse().ok()?;
+            let b: u8 = params.get(3)?.parse().ok()?;
+            Some((Color::Rgb(r, g, b), 4))
+        }
+        _ => None,
+    }
+}
+
+fn palette_color(index: u16, bright: bool) -> Color {
+    match (index, bright) {
+        (0, false) => Color::Black,
+        (1, false) => Color::Red,
+        (2, false) => Color::Green,
+        (3, false) => Color::Yellow,
+        (4, false) => Color::Blue,
+        (5, false) => Color::Magenta,
+        (6, false) => Color::Cyan,
+        (7, false) => Color::Gray,
+        (0, true) => Color::DarkGray,
+        (1, true) => Color::LightRed,
+        (2, true) => Color::LightGreen,
+        (3, true) => Color::LightYellow,
+        (4, true) => Color::LightBlue,
+        (5, true) => Color::LightMagenta,
+        (6, true) => Color::LightCyan,
+        (7, true) => Color::White,
+        _ => Color::Reset,
+    }
+}
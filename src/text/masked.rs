@@ -0,0 +1,80 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::{Span, Spans, Text};
+
+/// A contiguous stretch of text whose content should not be displayed as-is, such as a password
+/// or other secret value.
+///
+/// A [`Masked`] wraps the real content and a `mask_char`. When converted into a [`Span`],
+/// [`Spans`] or [`Text`] (or printed via [`Display`](fmt::Display)), every grapheme of the
+/// content is replaced by `mask_char` repeated enough times to preserve the original display
+/// width, so that wide glyphs still occupy the same number of cells once hidden.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use tui::text::{Masked, Span};
+/// let password = Masked::new("p@ssw0rd", '*');
+/// assert_eq!(8, password.to_string().len());
+/// assert_eq!(Span::raw("********"), Span::from(password));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Masked<'a> {
+    content: Cow<'a, str>,
+    mask_char: char,
+}
+
+impl<'a> Masked<'a> {
+    /// Creates a new [`Masked`] text from the given content, to be displayed as repeated
+    /// `mask_char`.
+    pub fn new<T>(content: T, mask_char: char) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        Self {
+            content: content.into(),
+            mask_char,
+        }
+    }
+
+    /// Returns the underlying, unmasked content.
+    pub fn value(&self) -> &str {
+        &self.content
+    }
+
+    fn masked_string(&self) -> String {
+        self.content
+            .as_ref()
+            .graphemes(true)
+            .map(|g| self.mask_char.to_string().repeat(g.width()))
+            .collect()
+    }
+}
+
+impl<'a> fmt::Display for Masked<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.masked_string())
+    }
+}
+
+impl<'a> From<Masked<'a>> for Span<'a> {
+    fn from(masked: Masked<'a>) -> Span<'a> {
+        Span::raw(masked.to_string())
+    }
+}
+
+impl<'a> From<Masked<'a>> for Spans<'a> {
+    fn from(masked: Masked<'a>) -> Spans<'a> {
+        Spans::from(Span::from(masked))
+    }
+}
+
+impl<'a> From<Masked<'a>> for Text<'a> {
+    fn from(masked: Masked<'a>) -> Text<'a> {
+        Text::from(Span::from(masked))
+    }
+}
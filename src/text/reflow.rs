@@ -0,0 +1,208 @@
+//! Breaking a stream of styled graphemes into terminal-width-bounded lines.
+//!
+//! [`LineComposer`] is the common interface; [`WordWrapper`] wraps on word boundaries (the
+//! historical behavior of [`super::WrappedText`]) and [`LineTruncator`] clips each line instead
+//! of wrapping it, optionally scrolling it horizontally.
+
+use std::collections::VecDeque;
+
+use unicode_width::UnicodeWidthStr;
+
+use super::StyledGrapheme;
+
+const NBSP: &str = "\u{00a0}";
+
+/// A state machine that paginates a stream of [`StyledGrapheme`] into lines no wider than a
+/// given width.
+///
+/// This cannot be an [`Iterator`] since the yielded slices borrow from the composer's own
+/// buffer (a streaming iterator would be needed for that), so callers drive it by repeatedly
+/// calling [`next_line`](LineComposer::next_line) until it returns `None`.
+pub trait LineComposer<'a> {
+    /// Returns the next line and its display width, or `None` once the input is exhausted.
+    fn next_line(&mut self) -> Option<(&[StyledGrapheme<'a>], u16)>;
+}
+
+pub(crate) fn is_whitespace(symbol: &str) -> bool {
+    symbol != NBSP && symbol.chars().all(char::is_whitespace)
+}
+
+/// Wraps a stream of styled graphemes onto word boundaries.
+///
+/// Leading whitespace on a line is dropped when `trim` is enabled, NBSP never counts as a break
+/// point (so it joins the words on either side of it), and a single grapheme wider than
+/// `max_line_width` is force-broken onto a line of its own.
+pub struct WordWrapper<'a, 'b> {
+    symbols: &'b mut dyn Iterator<Item = StyledGrapheme<'a>>,
+    max_line_width: u16,
+    trim: bool,
+    current_line: Vec<StyledGrapheme<'a>>,
+    pending: VecDeque<StyledGrapheme<'a>>,
+}
+
+impl<'a, 'b> WordWrapper<'a, 'b> {
+    pub fn new(
+        symbols: &'b mut dyn Iterator<Item = StyledGrapheme<'a>>,
+        max_line_width: u16,
+        trim: bool,
+    ) -> WordWrapper<'a, 'b> {
+        WordWrapper {
+            symbols,
+            max_line_width,
+            trim,
+            current_line: vec![],
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn pull(&mut self) -> Option<StyledGrapheme<'a>> {
+        self.pending.pop_front().or_else(|| self.symbols.next())
+    }
+}
+
+impl<'a, 'b> LineComposer<'a> for WordWrapper<'a, 'b> {
+    fn next_line(&mut self) -> Option<(&[StyledGrapheme<'a>], u16)> {
+        if self.max_line_width == 0 {
+            return None;
+        }
+        self.current_line.clear();
+
+        let mut last_word_end = 0;
+        let mut prev_whitespace = false;
+        let mut pulled_any = false;
+
+        while let Some(grapheme) = self.pull() {
+            pulled_any = true;
+            let whitespace = is_whitespace(grapheme.symbol);
+
+            // Skip leading whitespace when trim is enabled.
+            if self.current_line.is_empty() && self.trim && whitespace {
+                continue;
+            }
+
+            // Break on newline and discard it.
+            if grapheme.symbol == "\n" {
+                break;
+            }
+
+            let grapheme_width = grapheme.symbol.width() as u16;
+
+            // A grapheme that can never share a line with anything else is force-broken onto
+            // one of its own.
+            if grapheme_width > self.max_line_width {
+                if self.current_line.is_empty() {
+                    self.current_line.push(grapheme);
+                } else {
+                    self.pending.push_front(grapheme);
+                }
+                break;
+            }
+
+            if whitespace && !prev_whitespace {
+                last_word_end = self.current_line.len();
+            }
+
+            let current_width = self
+                .current_line
+                .iter()
+                .map(|g| g.symbol.width() as u16)
+                .sum::<u16>();
+            if current_width + grapheme_width > self.max_line_width {
+                self.pending.push_front(grapheme);
+                if last_word_end > 0 {
+                    for overflow in self.current_line.split_off(last_word_end).into_iter().rev() {
+                        self.pending.push_front(overflow);
+                    }
+                }
+                break;
+            }
+
+            prev_whitespace = whitespace;
+            self.current_line.push(grapheme);
+        }
+
+        if !pulled_any && self.current_line.is_empty() {
+            return None;
+        }
+        let width = self
+            .current_line
+            .iter()
+            .map(|g| g.symbol.width() as u16)
+            .sum();
+        Some((&self.current_line, width))
+    }
+}
+
+/// Clips each input line to `max_line_width` without wrapping, optionally scrolling it
+/// horizontally by `horizontal_offset` columns.
+pub struct LineTruncator<'a, 'b> {
+    symbols: &'b mut dyn Iterator<Item = StyledGrapheme<'a>>,
+    max_line_width: u16,
+    horizontal_offset: u16,
+    current_line: Vec<StyledGrapheme<'a>>,
+}
+
+impl<'a, 'b> LineTruncator<'a, 'b> {
+    pub fn new(
+        symbols: &'b mut dyn Iterator<Item = StyledGrapheme<'a>>,
+        max_line_width: u16,
+    ) -> LineTruncator<'a, 'b> {
+        LineTruncator {
+            symbols,
+            max_line_width,
+            horizontal_offset: 0,
+            current_line: vec![],
+        }
+    }
+
+    /// Sets the number of columns to skip from the start of each line, to support horizontal
+    /// scrolling of long lines.
+    pub fn set_horizontal_offset(&mut self, horizontal_offset: u16) {
+        self.horizontal_offset = horizontal_offset;
+    }
+}
+
+impl<'a, 'b> LineComposer<'a> for LineTruncator<'a, 'b> {
+    fn next_line(&mut self) -> Option<(&[StyledGrapheme<'a>], u16)> {
+        if self.max_line_width == 0 {
+            return None;
+        }
+        self.current_line.clear();
+
+        let mut skip_remaining = self.horizontal_offset;
+        let mut current_width = 0u16;
+        let mut pulled_any = false;
+        let mut discarding = false;
+
+        while let Some(grapheme) = self.symbols.next() {
+            pulled_any = true;
+
+            if grapheme.symbol == "\n" {
+                break;
+            }
+            if discarding {
+                continue;
+            }
+
+            let grapheme_width = grapheme.symbol.width() as u16;
+
+            if skip_remaining > 0 {
+                skip_remaining = skip_remaining.saturating_sub(grapheme_width);
+                continue;
+            }
+
+            if current_width + grapheme_width > self.max_line_width {
+                discarding = true;
+                continue;
+            }
+
+            current_width += grapheme_width;
+            self.current_line.push(grapheme);
+        }
+
+        if !pulled_any && self.current_line.is_empty() {
+            return None;
+        }
+        Some((&self.current_line, current_width))
+    }
+}